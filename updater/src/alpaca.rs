@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-mod params {
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::Message;
+
+pub mod params {
     #[derive(strum_macros::Display, strum_macros::EnumString, Copy, Clone, Debug)]
     #[strum(serialize_all = "lowercase")]
     pub enum Feed {
@@ -299,4 +302,111 @@ impl AlpacaClient {
             }
         }
     }
+
+    /// Subscribes to Alpaca's live bars feed over websocket and yields each
+    /// `(symbol, Bar)` as it arrives. Unlike `stream_bars`, this is a true
+    /// push subscription, not paginated history.
+    pub fn stream_live_bars(
+        &self,
+        symbols: Vec<String>,
+        feed: params::Feed,
+    ) -> impl tokio_stream::Stream<Item = Result<(String, Bar), Box<dyn std::error::Error>>> + '_
+    {
+        async_stream::stream! {
+            let url = format!("wss://stream.data.alpaca.markets/v2/{feed}");
+
+            let (ws, _) = match tokio_tungstenite::connect_async(&url).await {
+                Ok(conn) => conn,
+                Err(e) => { yield Err(e.into()); return; }
+            };
+            let (mut write, mut read) = ws.split();
+
+            let auth = serde_json::json!({
+                "action": "auth",
+                "key": self.api_key_id,
+                "secret": self.api_secret_key,
+            });
+            if let Err(e) = write.send(Message::Text(auth.to_string())).await {
+                yield Err(e.into());
+                return;
+            }
+
+            let subscribe = serde_json::json!({ "action": "subscribe", "bars": symbols });
+            if let Err(e) = write.send(Message::Text(subscribe.to_string())).await {
+                yield Err(e.into());
+                return;
+            }
+
+            while let Some(message) = read.next().await {
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(_) => continue,
+                    Err(e) => { yield Err(e.into()); break; }
+                };
+
+                match serde_json::from_str::<Vec<serde_json::Value>>(&text) {
+                    Ok(messages) => {
+                        for msg in messages {
+                            match msg.get("T").and_then(serde_json::Value::as_str) {
+                                Some("b") => match serde_json::from_value::<LiveBarMessage>(msg) {
+                                    Ok(bar) => yield Ok((bar.symbol.clone(), bar.into_bar())),
+                                    Err(e) => yield Err(e.into()),
+                                },
+                                Some("error") => {
+                                    let code = msg.get("code").and_then(serde_json::Value::as_i64).unwrap_or_default();
+                                    let reason = msg.get("msg").and_then(serde_json::Value::as_str).unwrap_or("unknown error");
+                                    yield Err(format!("Alpaca stream error {code}: {reason}").into());
+                                }
+                                // Auth/subscription acks ("success", "subscription")
+                                // carry no OHLCV fields; nothing to yield for them.
+                                _ => {}
+                            }
+                        }
+                    }
+                    Err(e) => yield Err(e.into()),
+                }
+            }
+        }
+    }
+}
+
+/// One message off Alpaca's live bars websocket, e.g.
+/// `{"T":"b","S":"AAPL","o":1,"h":2,"l":0.5,"c":1.5,"v":100,"vw":1.2,"n":10,"t":"2024-01-01T00:00:00Z"}`.
+#[derive(Debug, serde::Deserialize)]
+struct LiveBarMessage {
+    #[serde(rename = "T")]
+    msg_type: String,
+    #[serde(rename = "S")]
+    symbol: String,
+    #[serde(rename = "o")]
+    o: f64,
+    #[serde(rename = "h")]
+    h: f64,
+    #[serde(rename = "l")]
+    l: f64,
+    #[serde(rename = "c")]
+    c: f64,
+    #[serde(rename = "v")]
+    v: f64,
+    #[serde(rename = "vw")]
+    vw: f64,
+    #[serde(rename = "n")]
+    n: u64,
+    #[serde(rename = "t")]
+    t: String,
+}
+
+impl LiveBarMessage {
+    fn into_bar(self) -> Bar {
+        Bar {
+            t: self.t,
+            o: self.o,
+            h: self.h,
+            l: self.l,
+            c: self.c,
+            v: self.v,
+            vw: self.vw,
+            n: self.n,
+        }
+    }
 }
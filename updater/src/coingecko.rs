@@ -0,0 +1,56 @@
+use std::error::Error;
+
+/// Minimal client for CoinGecko's public market-data endpoints. Unlike
+/// Yahoo/Alpaca, CoinGecko addresses coins by an id (e.g. `bitcoin`), not a
+/// ticker, so callers must resolve that id themselves (see `Asset::coingecko_id`).
+pub struct CoinGeckoClient {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MarketChartResponse {
+    pub prices: Vec<[f64; 2]>,
+}
+
+impl CoinGeckoClient {
+    const URL: &'static str = "https://api.coingecko.com/api/v3";
+
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    /// Fetches up to `days` of daily close prices for `coin_id`, priced in
+    /// `vs_currency`.
+    pub async fn fetch_market_chart(
+        &self,
+        coin_id: &str,
+        vs_currency: &str,
+        days: u32,
+    ) -> Result<MarketChartResponse, Box<dyn Error>> {
+        let mut request = self
+            .client
+            .get(format!("{}/coins/{coin_id}/market_chart", Self::URL))
+            .query(&[
+                ("vs_currency", vs_currency.to_string()),
+                ("days", days.to_string()),
+                ("interval", "daily".to_string()),
+            ]);
+
+        if let Some(key) = &self.api_key {
+            request = request.header("x-cg-demo-api-key", key);
+        }
+
+        let response = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<MarketChartResponse>()
+            .await?;
+
+        Ok(response)
+    }
+}
@@ -2,21 +2,44 @@ use sqlx::{
     Pool, Postgres,
     postgres::{PgPoolOptions, PgQueryResult},
 };
-use std::{env::var, error::Error, ffi::FromBytesUntilNulError, time::Duration};
+use std::{
+    env::var,
+    error::Error,
+    ffi::FromBytesUntilNulError,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
 use time::{Date, UtcDateTime};
 use tokio::join;
-
+use tokio_stream::StreamExt;
+
+use crate::alpaca::{AlpacaClient, params};
+use crate::coingecko::CoinGeckoClient;
+use crate::provider::{ProviderKind, provider_for};
+use crate::resample::Resampler;
+use crate::scheduler::Scheduler;
+use crate::wal::{Wal, WalBatch};
 use crate::yf::PriceFrame;
 mod alpaca;
+mod coingecko;
+mod provider;
+mod resample;
+mod scheduler;
+mod wal;
 mod yf;
 
 const PRICE_MAX: usize = 10_000;
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const WAL_PATH: &str = "updater.wal";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Asset {
     id: i64,
     symbol: String,
     last_updated: Option<time::Date>,
+    provider: Option<String>,
+    coingecko_id: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -40,36 +63,127 @@ impl Prices {
 
 struct Updater {
     timeout: Duration,
+    db_url: String,
     conn: Pool<Postgres>,
     today: UtcDateTime,
     prices: PriceFrame,
     assets: Vec<Asset>,
     updated_ids: Vec<i64>,
     max_retries: u32,
+    alpaca: AlpacaClient,
+    coingecko: CoinGeckoClient,
+    resampler: Resampler,
+    wal: Wal,
+    live_bars: std::collections::HashMap<(i64, Date), BarAgg>,
+    /// Latest date already staged+cleared from `live_bars` per asset, so a
+    /// bar that straggles in after its bucket was flushed doesn't spawn a
+    /// new single-bar bucket that clobbers the aggregated row on its own
+    /// eventual flush.
+    finalized_through: std::collections::HashMap<i64, Date>,
+}
+
+/// Running OHLCV aggregate for one `(asset_id, date)` bucket being built up
+/// from individual live bars, so a merge (not a raw overwrite) is what
+/// eventually reaches `insert_price_frame`.
+#[derive(Debug, Clone, Copy)]
+struct BarAgg {
+    open: f32,
+    close: f32,
+    high: f32,
+    low: f32,
+    volume: i64,
+    first_seen: time::OffsetDateTime,
+    last_seen: time::OffsetDateTime,
+}
+
+impl BarAgg {
+    fn new(bar: &alpaca::Bar, tstamp: time::OffsetDateTime) -> Self {
+        Self {
+            open: bar.o as f32,
+            close: bar.c as f32,
+            high: bar.h as f32,
+            low: bar.l as f32,
+            volume: bar.v as i64,
+            first_seen: tstamp,
+            last_seen: tstamp,
+        }
+    }
+
+    /// Folds in a bar that may have arrived late or out of order:
+    /// `open`/`close` only move when the incoming bar is chronologically
+    /// first/last so far, `high`/`low` track the running extent, and
+    /// `volume` accumulates.
+    fn merge(&mut self, bar: &alpaca::Bar, tstamp: time::OffsetDateTime) {
+        if tstamp < self.first_seen {
+            self.open = bar.o as f32;
+            self.first_seen = tstamp;
+        }
+        if tstamp > self.last_seen {
+            self.close = bar.c as f32;
+            self.last_seen = tstamp;
+        }
+        self.high = self.high.max(bar.h as f32);
+        self.low = self.low.min(bar.l as f32);
+        self.volume += bar.v as i64;
+    }
 }
 
 impl Updater {
-    pub async fn new(db_url: &str, timeout: Duration) -> Result<Self, Box<dyn Error>> {
+    /// `wal_role` namespaces the WAL file (e.g. `"daemon"` vs. `"live"`) so
+    /// two `Updater`s running concurrently never `spill`/`drain` the same
+    /// file — `Wal` does no locking of its own.
+    pub async fn new(
+        db_url: &str,
+        timeout: Duration,
+        wal_role: &str,
+    ) -> Result<Self, Box<dyn Error>> {
         let conn = PgPoolOptions::new().connect(&db_url).await?;
+        let alpaca = AlpacaClient::new(
+            var("ALPACA_API_KEY_ID").unwrap_or_default(),
+            var("ALPACA_API_SECRET_KEY").unwrap_or_default(),
+        );
+        let coingecko = CoinGeckoClient::new(var("COINGECKO_API_KEY").ok());
+        let resampler = Resampler::new(conn.clone());
+        let wal_base = var("UPDATER_WAL_PATH").unwrap_or_else(|_| WAL_PATH.to_string());
+        let wal = Wal::new(format!("{wal_base}.{wal_role}"));
         Ok(Self {
             max_retries: 4,
+            db_url: db_url.to_string(),
             conn,
             today: UtcDateTime::now(),
             prices: PriceFrame::empty(),
             assets: vec![],
             updated_ids: vec![],
             timeout,
+            alpaca,
+            coingecko,
+            resampler,
+            wal,
+            live_bars: std::collections::HashMap::new(),
+            finalized_through: std::collections::HashMap::new(),
         })
     }
 
+    /// Drops and reacquires the pool after a broken-connection error, rather
+    /// than treating it as fatal.
+    async fn reconnect(&mut self) -> Result<(), sqlx::Error> {
+        let conn = PgPoolOptions::new().connect(&self.db_url).await?;
+        self.resampler = Resampler::new(conn.clone());
+        self.conn = conn;
+        Ok(())
+    }
+
     fn update_date(&mut self) {
         self.today = UtcDateTime::now();
     }
 
     async fn fetch_assets(&mut self) -> Result<(), sqlx::Error> {
-        let assets = sqlx::query_as!(Asset, "select symbol, id, last_updated from api.assets")
-            .fetch_all(&self.conn)
-            .await?;
+        let assets = sqlx::query_as!(
+            Asset,
+            "select symbol, id, last_updated, provider, coingecko_id from api.assets"
+        )
+        .fetch_all(&self.conn)
+        .await?;
 
         self.assets = assets;
         Ok(())
@@ -96,27 +210,35 @@ impl Updater {
             id,
             symbol,
             last_updated,
+            provider,
+            coingecko_id,
         }: &Asset,
         max_retries: u32,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let today = self.today;
 
         let range = Self::mk_range(today, *last_updated);
+        let kind = provider.as_deref().and_then(ProviderKind::parse);
+        let provider = provider_for(kind, &self.alpaca, &self.coingecko);
 
-        for attempt in 1..(max_retries + 1) {
-            // new client with new UA per stock - rate limiting
-            let client = yf::client()?;
+        // CoinGecko addresses coins by id, not ticker, so route through the
+        // mapping column instead of the asset's regular symbol.
+        let provider_symbol = if kind == Some(ProviderKind::CoinGecko) {
+            coingecko_id.as_deref().unwrap_or(symbol)
+        } else {
+            symbol
+        };
 
+        for attempt in 1..(max_retries + 1) {
             // jitter to evade rate limiting
             let jitter = Duration::from_millis(rand::random::<u64>() % 1000);
             let timeout = self.timeout + jitter;
 
-            let fut = yf::fetch_for_symbol(&client, &symbol, range.as_ref(), "1d");
+            let fut = provider.fetch(*id, provider_symbol, range);
             let (result, _) = join!(fut, tokio::time::sleep(timeout));
 
             match result {
-                Ok(mut data) => {
-                    let mut frame = data.extract_time_series(*id)?;
+                Ok(mut frame) => {
                     self.prices.extend(&mut frame);
                     return Ok(());
                 }
@@ -167,58 +289,395 @@ impl Updater {
         .await
     }
 
-    async fn inserter(&mut self) -> Result<(), sqlx::Error> {
-        println!("inserting..");
+    /// Retries `op` with exponential backoff, transparently re-acquiring the
+    /// pool connection when the error looks like a dropped connection
+    /// instead of treating it as fatal.
+    ///
+    /// `op` returns a boxed future rather than a bare `impl Future` because a
+    /// `FnMut(&Self) -> Fut` bound with a non-lifetime-parameterized `Fut`
+    /// would force every call site's closure to produce a future that
+    /// outlives the borrow of `self` it's built from, which none of them do.
+    async fn with_db_retry<T>(
+        &mut self,
+        mut op: impl FnMut(&Self) -> Pin<Box<dyn Future<Output = Result<T, sqlx::Error>> + '_>>,
+    ) -> Result<T, sqlx::Error> {
+        let mut last_err = None;
 
-        let t0 = std::time::Instant::now();
+        for attempt in 1..=self.max_retries {
+            match op(self).await {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    if is_broken_connection(&e) {
+                        if let Err(reconnect_err) = self.reconnect().await {
+                            eprintln!("Failed to reconnect: {reconnect_err}");
+                        }
+                    }
+                    eprintln!("DB op failed (attempt {attempt}/{}): {e}", self.max_retries);
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                    last_err = Some(e);
+                }
+            }
+        }
 
-        self.insert_price_frame().await?;
+        Err(last_err.expect("loop runs at least once"))
+    }
 
-        let t1 = std::time::Instant::now();
+    async fn inserter(&mut self) -> Result<(), Box<dyn Error>> {
+        println!("inserting..");
 
-        let dt = t1 - t0;
+        let t0 = std::time::Instant::now();
 
+        if let Err(e) = self
+            .with_db_retry(|this| Box::pin(this.insert_price_frame()))
+            .await
+        {
+            eprintln!("Exhausted retries inserting prices, spilling to WAL: {e}");
+            self.wal.spill(&WalBatch::capture(
+                &self.prices.asset_id,
+                &self.prices.open,
+                &self.prices.close,
+                &self.prices.high,
+                &self.prices.low,
+                &self.prices.volume,
+                &self.prices.tstamp,
+            ))?;
+            self.prices.clear();
+            return Err(Box::new(e));
+        }
+
+        let dt = t0.elapsed();
         println!("{}ms", dt.as_millis());
 
+        // Bound the resample to just the buckets this batch touched, before
+        // the buffers are cleared below.
+        let resample_range = self
+            .prices
+            .tstamp
+            .iter()
+            .copied()
+            .fold(None, |range: Option<(Date, Date)>, ts| {
+                Some(range.map_or((ts, ts), |(min, max)| (min.min(ts), max.max(ts))))
+            });
+
+        // Only clear the buffers once the insert has actually committed.
         self.prices.clear();
-        self.mark_updated().await?;
+
+        self.with_db_retry(|this| Box::pin(this.mark_updated())).await?;
         self.updated_ids.clear();
+
+        let Some((since, until)) = resample_range else {
+            return Ok(());
+        };
+
+        for tf in [params::Timeframe::Week, params::Timeframe::Months(1)] {
+            if let Err(e) = self.resampler.resample(tf, since, until).await {
+                eprintln!("Error resampling {tf}: {e}")
+            }
+        }
+
         Ok(())
     }
 
-    pub async fn update(&mut self) -> Result<(), Box<dyn Error>> {
-        self.update_date();
+    /// Consumes Alpaca's live bars websocket and folds each bar into an
+    /// in-process per-`(asset_id, date)` aggregate instead of upserting it
+    /// directly: a raw per-bar upsert into `api.asset_prices` would replace
+    /// the whole day's `open`/`high`/`low`/`volume` with just that one
+    /// minute's values. `BarAgg::merge` keeps `open`/`close` anchored to the
+    /// chronologically first/last bar seen, `high`/`low` as running
+    /// extents, and `volume` as a running sum, so a late or reordered bar
+    /// merges into the day instead of stomping it. A bucket is only staged
+    /// for `insert_price_frame` once the stream has moved on to a later
+    /// date for that asset, so each day is written once, fully aggregated.
+    /// Stops once `stop` resolves, finalizing and flushing every bucket
+    /// still open at that point so a shutdown doesn't drop today's bars.
+    pub async fn run_live_stream(
+        &mut self,
+        symbols: Vec<String>,
+        feed: params::Feed,
+        stop: impl std::future::Future<Output = ()>,
+    ) -> Result<(), Box<dyn Error>> {
         self.fetch_assets().await?;
+        let symbols_by_asset: std::collections::HashMap<String, i64> = self
+            .assets
+            .iter()
+            .filter(|a| symbols.contains(&a.symbol))
+            .map(|a| (a.symbol.clone(), a.id))
+            .collect();
+
+        let mut stream = self.alpaca.stream_live_bars(symbols, feed);
+        tokio::pin!(stop);
+
+        loop {
+            let item = tokio::select! {
+                item = stream.next() => item,
+                _ = &mut stop => break,
+            };
+            let Some(item) = item else { break };
 
-        while let Some(asset) = self.assets.pop() {
-            print!("{}", asset.symbol);
-            if let Err(e) = self.fetch_prices_for_asset(&asset, self.max_retries).await {
-                eprintln!("Error fetching prices for {}: {}", asset.symbol, e);
+            let (symbol, bar) = match item {
+                Ok(item) => item,
+                Err(e) => {
+                    eprintln!("Error reading live bar: {e}");
+                    continue;
+                }
+            };
+
+            let Some(&asset_id) = symbols_by_asset.get(&symbol) else {
                 continue;
             };
 
-            print!("- [{}/{PRICE_MAX}]\n", self.prices.length);
+            let tstamp = time::OffsetDateTime::parse(
+                &bar.t,
+                &time::format_description::well_known::Rfc3339,
+            )?;
+            let date = tstamp.date();
+
+            if let Some(&through) = self.finalized_through.get(&asset_id) {
+                if date <= through {
+                    eprintln!(
+                        "Dropping stale live bar for asset {asset_id} on {date}, already flushed through {through}"
+                    );
+                    continue;
+                }
+            }
+
+            self.finalize_stale_buckets(asset_id, date);
+
+            self.live_bars
+                .entry((asset_id, date))
+                .and_modify(|agg| agg.merge(&bar, tstamp))
+                .or_insert_with(|| BarAgg::new(&bar, tstamp));
 
             if self.prices.length >= PRICE_MAX {
                 if let Err(e) = self.inserter().await {
-                    eprintln!("Error inserting assets: {e}")
+                    eprintln!("Error inserting live bars: {e}")
                 }
             }
+        }
 
-            self.updated_ids.push(asset.id);
+        self.finalize_all_buckets();
+        if self.prices.length > 0 {
+            if let Err(e) = self.inserter().await {
+                eprintln!("Error inserting live bars: {e}")
+            }
         }
 
-        println!("inserting remaining..");
-        if let Err(e) = self.inserter().await {
-            eprintln!("Error inserting assets: {e}")
+        Ok(())
+    }
+
+    /// Moves any bucket for `asset_id` strictly older than `date` out of
+    /// `live_bars` and into `self.prices`: the stream has moved on to a new
+    /// day for that asset, so the old day is done accumulating. Callers must
+    /// first check `finalized_through` so a bar for an already-flushed date
+    /// doesn't spawn a fresh bucket here instead of being dropped.
+    fn finalize_stale_buckets(&mut self, asset_id: i64, date: Date) {
+        let stale: Vec<_> = self
+            .live_bars
+            .keys()
+            .filter(|(id, d)| *id == asset_id && *d < date)
+            .copied()
+            .collect();
+
+        for key in stale {
+            if let Some(agg) = self.live_bars.remove(&key) {
+                self.stage_bar(key, agg);
+            }
+        }
+    }
+
+    /// Flushes every outstanding bucket, e.g. when the stream ends.
+    fn finalize_all_buckets(&mut self) {
+        for (key, agg) in self.live_bars.drain().collect::<Vec<_>>() {
+            self.stage_bar(key, agg);
+        }
+    }
+
+    fn stage_bar(&mut self, (asset_id, date): (i64, Date), agg: BarAgg) {
+        self.finalized_through
+            .entry(asset_id)
+            .and_modify(|through| *through = (*through).max(date))
+            .or_insert(date);
+
+        self.prices.asset_id.push(asset_id);
+        self.prices.open.push(Some(agg.open));
+        self.prices.close.push(Some(agg.close));
+        self.prices.high.push(Some(agg.high));
+        self.prices.low.push(Some(agg.low));
+        self.prices.volume.push(Some(agg.volume));
+        self.prices.tstamp.push(date);
+        self.prices.length += 1;
+    }
+
+    /// Replays any batches a previous run couldn't commit before it exited.
+    pub async fn replay_wal(&mut self) -> Result<(), Box<dyn Error>> {
+        for batch in self.wal.drain()? {
+            let result = self
+                .with_db_retry(|this| Box::pin(insert_wal_batch(&this.conn, &batch)))
+                .await;
+
+            if let Err(e) = result {
+                eprintln!("Replaying WAL batch failed again, re-spilling: {e}");
+                self.wal.spill(&batch)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn asset(&self, id: i64) -> Option<&Asset> {
+        self.assets.iter().find(|a| a.id == id)
+    }
+
+    /// Runs forever, fetching whichever asset's `next_run` is due, then
+    /// requeuing it a cadence out (or with backoff on failure). Refills the
+    /// queue from the DB whenever it drains. Stops once `stop` resolves.
+    pub async fn run_daemon(
+        &mut self,
+        mut scheduler: Scheduler,
+        market_cadence: Duration,
+        overnight_cadence: Duration,
+        stop: impl std::future::Future<Output = ()>,
+    ) -> Result<(), Box<dyn Error>> {
+        tokio::pin!(stop);
+
+        let refill = |scheduler: &mut Scheduler, updater: &mut Self| {
+            scheduler.set_cadence(if is_market_hours(updater.today) {
+                market_cadence
+            } else {
+                overnight_cadence
+            });
+            scheduler.refill(updater.assets.iter().map(|a| a.id), Instant::now());
+        };
+
+        self.update_date();
+        self.fetch_assets().await?;
+        refill(&mut scheduler, self);
+
+        loop {
+            if scheduler.is_empty() {
+                self.update_date();
+                self.fetch_assets().await?;
+                refill(&mut scheduler, self);
+
+                // The assets table came back empty (or every row failed to
+                // refill for some other reason) — without this, an empty
+                // scheduler spins straight back into `fetch_assets` with no
+                // sleep and no `stop` check, hammering the DB forever.
+                if scheduler.is_empty() {
+                    tokio::select! {
+                        _ = tokio::time::sleep(DEFAULT_POLL_INTERVAL) => {}
+                        _ = &mut stop => break,
+                    }
+                    continue;
+                }
+            }
+
+            let now = Instant::now();
+            match scheduler.pop_due(now) {
+                Some(id) => {
+                    let Some(asset) = self.asset(id).cloned() else {
+                        continue;
+                    };
+
+                    match self.fetch_prices_for_asset(&asset, self.max_retries).await {
+                        Ok(()) => {
+                            self.updated_ids.push(id);
+                            scheduler.reschedule(id, now);
+                        }
+                        Err(e) => {
+                            eprintln!("Error fetching prices for {}: {e}", asset.symbol);
+                            scheduler.reschedule_after_failure(id, now);
+                        }
+                    }
+
+                    if self.prices.length >= PRICE_MAX {
+                        if let Err(e) = self.inserter().await {
+                            eprintln!("Error inserting assets: {e}")
+                        }
+                    }
+                }
+                None => {
+                    let wake = scheduler
+                        .next_wake()
+                        .unwrap_or_else(|| now + DEFAULT_POLL_INTERVAL);
+                    let wake = tokio::time::Instant::from_std(wake);
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(wake) => {}
+                        _ = &mut stop => break,
+                    }
+                }
+            }
+        }
+
+        if self.prices.length > 0 {
+            if let Err(e) = self.inserter().await {
+                eprintln!("Error inserting assets: {e}")
+            }
         }
 
-        self.assets.clear();
-        self.prices.clear();
         Ok(())
     }
 }
 
+/// Same upsert as `insert_price_frame`, replayed from a `WalBatch` instead
+/// of the live `PriceFrame` buffer.
+async fn insert_wal_batch(
+    conn: &Pool<Postgres>,
+    batch: &WalBatch,
+) -> Result<PgQueryResult, sqlx::Error> {
+    sqlx::query!(
+        r#"
+            INSERT INTO api.asset_prices (asset_id, open, close, high, low, volume, tstamp)
+            SELECT * FROM UNNEST($1::bigint[], $2::real[], $3::real[], $4::real[], $5::real[], $6::bigint[], $7::date[])
+            ON CONFLICT (asset_id, tstamp)
+            DO UPDATE SET
+                open = EXCLUDED.open,
+                close = EXCLUDED.close,
+                high = EXCLUDED.high,
+                low = EXCLUDED.low,
+                volume = EXCLUDED.volume,
+                tstamp = EXCLUDED.tstamp
+        "#,
+        &batch.asset_id[..],
+        &batch.open as &[Option<f32>],
+        &batch.close as &[Option<f32>],
+        &batch.high as &[Option<f32>],
+        &batch.low as &[Option<f32>],
+        &batch.volume as &[Option<i64>],
+        &batch.tstamp as &[time::Date],
+    )
+    .execute(conn)
+    .await
+}
+
+/// Heuristic for "the connection dropped out from under us" vs. a regular
+/// query error, so only the former triggers a pool reacquire.
+fn is_broken_connection(e: &sqlx::Error) -> bool {
+    matches!(
+        e,
+        sqlx::Error::Io(_) | sqlx::Error::PoolClosed | sqlx::Error::PoolTimedOut
+    )
+}
+
+fn env_secs(key: &str, default: u64) -> Duration {
+    Duration::from_secs(var(key).ok().and_then(|s| s.parse().ok()).unwrap_or(default))
+}
+
+/// Very rough US-equities market-hours check in UTC, overridable via env so
+/// other markets/timezones can be plugged in without a code change.
+fn is_market_hours(now: UtcDateTime) -> bool {
+    let open = var("MARKET_OPEN_UTC_HOUR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(13);
+    let close = var("MARKET_CLOSE_UTC_HOUR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(21);
+
+    (open..close).contains(&now.hour())
+}
+
 #[tokio::main]
 async fn main() {
     let db_url = var("DATABASE_URL").expect("DATABASE_URL not given");
@@ -226,9 +685,64 @@ async fn main() {
     let timeout: f32 = var("YF_TIMEOUT").map_or(2f32, |s| s.parse().unwrap());
     let timeout = Duration::from_secs_f32(timeout);
 
-    let mut updater = Updater::new(&db_url, timeout).await.unwrap();
+    let market_cadence = env_secs("MARKET_HOURS_CADENCE_SECS", 5 * 60);
+    let overnight_cadence = env_secs("OVERNIGHT_CADENCE_SECS", 60 * 60);
+
+    let mut updater = Updater::new(&db_url, timeout, "daemon").await.unwrap();
 
-    if let Err(e) = updater.update().await {
+    if let Err(e) = updater.replay_wal().await {
+        eprintln!("Error replaying WAL: {e}");
+    }
+
+    let cadence = if is_market_hours(UtcDateTime::now()) {
+        market_cadence
+    } else {
+        overnight_cadence
+    };
+    let scheduler = Scheduler::new(cadence);
+
+    let mut live_task = None;
+    if let Ok(symbols) = var("ALPACA_LIVE_SYMBOLS") {
+        let symbols: Vec<String> = symbols.split(',').map(str::to_string).collect();
+        let mut live_updater = Updater::new(&db_url, timeout, "live").await.unwrap();
+
+        if let Err(e) = live_updater.replay_wal().await {
+            eprintln!("Error replaying live WAL: {e}");
+        }
+
+        let live_stop = async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("failed to listen for ctrl-c");
+        };
+
+        live_task = Some(tokio::spawn(async move {
+            if let Err(e) = live_updater
+                .run_live_stream(symbols, params::Feed::Iex, live_stop)
+                .await
+            {
+                eprintln!("Live bar stream exited: {e}");
+            }
+        }));
+    }
+
+    let stop = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to listen for ctrl-c");
+        println!("shutdown requested, flushing and exiting..");
+    };
+
+    if let Err(e) = updater
+        .run_daemon(scheduler, market_cadence, overnight_cadence, stop)
+        .await
+    {
         eprintln!("{e}");
     };
+
+    if let Some(task) = live_task {
+        if let Err(e) = task.await {
+            eprintln!("Live bar task panicked: {e}");
+        }
+    }
 }
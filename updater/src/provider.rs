@@ -0,0 +1,170 @@
+use std::error::Error;
+use time::UtcDateTime;
+
+use crate::alpaca::{self, AlpacaClient};
+use crate::coingecko::CoinGeckoClient;
+use crate::yf::{self, PriceFrame};
+
+/// A source of OHLCV bars that can be normalized into a [`PriceFrame`] for a
+/// single asset, regardless of whether the upstream API is Yahoo's quote
+/// chart, Alpaca's bars endpoint or CoinGecko's market chart.
+#[async_trait::async_trait]
+pub trait PriceProvider {
+    async fn fetch(
+        &self,
+        asset_id: i64,
+        symbol: &str,
+        range: Option<(UtcDateTime, UtcDateTime)>,
+    ) -> Result<PriceFrame, Box<dyn Error>>;
+}
+
+/// The provider tag stored in `api.assets.provider`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Yahoo,
+    Alpaca,
+    CoinGecko,
+}
+
+impl ProviderKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "yahoo" | "yf" => Some(Self::Yahoo),
+            "alpaca" => Some(Self::Alpaca),
+            "coingecko" | "crypto" => Some(Self::CoinGecko),
+            _ => None,
+        }
+    }
+}
+
+/// Builds the provider for an asset, defaulting to Yahoo when the column is
+/// missing or unrecognized so existing assets keep working unchanged.
+pub fn provider_for<'a>(
+    kind: Option<ProviderKind>,
+    alpaca: &'a AlpacaClient,
+    coingecko: &'a CoinGeckoClient,
+) -> Box<dyn PriceProvider + 'a> {
+    match kind.unwrap_or(ProviderKind::Yahoo) {
+        ProviderKind::Yahoo => Box::new(YahooProvider),
+        ProviderKind::Alpaca => Box::new(AlpacaProvider { client: alpaca }),
+        ProviderKind::CoinGecko => Box::new(CoinGeckoProvider { client: coingecko }),
+    }
+}
+
+pub struct YahooProvider;
+
+#[async_trait::async_trait]
+impl PriceProvider for YahooProvider {
+    async fn fetch(
+        &self,
+        asset_id: i64,
+        symbol: &str,
+        range: Option<(UtcDateTime, UtcDateTime)>,
+    ) -> Result<PriceFrame, Box<dyn Error>> {
+        let client = yf::client()?;
+        let mut data = yf::fetch_for_symbol(&client, symbol, range.as_ref(), "1d").await?;
+        data.extract_time_series(asset_id)
+    }
+}
+
+pub struct AlpacaProvider<'a> {
+    client: &'a AlpacaClient,
+}
+
+#[async_trait::async_trait]
+impl PriceProvider for AlpacaProvider<'_> {
+    async fn fetch(
+        &self,
+        asset_id: i64,
+        symbol: &str,
+        range: Option<(UtcDateTime, UtcDateTime)>,
+    ) -> Result<PriceFrame, Box<dyn Error>> {
+        let query = alpaca::QueryParams {
+            timeframe: Some(alpaca::params::Timeframe::Day),
+            start: range.map(|(start, _)| alpaca::params::DateTime::Date(start.date())),
+            end: range.map(|(_, end)| alpaca::params::DateTime::Date(end.date())),
+            ..Default::default()
+        };
+
+        let bars = self
+            .client
+            .fetch_all_bars(vec![symbol.to_string()], query)
+            .await?;
+
+        let bars = bars.get(symbol).map(Vec::as_slice).unwrap_or_default();
+        bars_to_frame(asset_id, bars)
+    }
+}
+
+pub struct CoinGeckoProvider<'a> {
+    client: &'a CoinGeckoClient,
+}
+
+#[async_trait::async_trait]
+impl PriceProvider for CoinGeckoProvider<'_> {
+    /// `symbol` here is the CoinGecko coin id (e.g. `bitcoin`), not a
+    /// ticker; see `Asset::coingecko_id`.
+    async fn fetch(
+        &self,
+        asset_id: i64,
+        symbol: &str,
+        range: Option<(UtcDateTime, UtcDateTime)>,
+    ) -> Result<PriceFrame, Box<dyn Error>> {
+        let days = range
+            .map(|(start, end)| (end.date() - start.date()).whole_days().max(1) as u32)
+            .unwrap_or(30);
+
+        let chart = self.client.fetch_market_chart(symbol, "usd", days).await?;
+        market_chart_to_frame(asset_id, &chart.prices)
+    }
+}
+
+/// CoinGecko only reports a close per sample point (no OHLC on the free
+/// tier), so open/high/low all fall back to that close and volume is unset.
+fn market_chart_to_frame(
+    asset_id: i64,
+    prices: &[[f64; 2]],
+) -> Result<PriceFrame, Box<dyn Error>> {
+    let mut frame = PriceFrame::empty();
+
+    for [ms, price] in prices {
+        let tstamp = time::OffsetDateTime::from_unix_timestamp((*ms as i64) / 1000)?.date();
+        let price = Some(*price as f32);
+
+        frame.asset_id.push(asset_id);
+        frame.open.push(price);
+        frame.close.push(price);
+        frame.high.push(price);
+        frame.low.push(price);
+        frame.volume.push(None);
+        frame.tstamp.push(tstamp);
+        frame.length += 1;
+    }
+
+    Ok(frame)
+}
+
+/// Normalizes Alpaca's `Bar` rows onto the same shape the Yahoo client
+/// produces, so both providers feed `insert_price_frame` unchanged.
+fn bars_to_frame(asset_id: i64, bars: &[alpaca::Bar]) -> Result<PriceFrame, Box<dyn Error>> {
+    let mut frame = PriceFrame::empty();
+
+    for bar in bars {
+        let tstamp = time::OffsetDateTime::parse(
+            &bar.t,
+            &time::format_description::well_known::Rfc3339,
+        )?
+        .date();
+
+        frame.asset_id.push(asset_id);
+        frame.open.push(Some(bar.o as f32));
+        frame.close.push(Some(bar.c as f32));
+        frame.high.push(Some(bar.h as f32));
+        frame.low.push(Some(bar.l as f32));
+        frame.volume.push(Some(bar.v as i64));
+        frame.tstamp.push(tstamp);
+        frame.length += 1;
+    }
+
+    Ok(frame)
+}
@@ -0,0 +1,81 @@
+use sqlx::{Pool, Postgres, postgres::PgQueryResult};
+
+use crate::alpaca::params;
+
+/// Derives coarser candles from the daily rows already sitting in
+/// `api.asset_prices`, writing them into `api.asset_candles` keyed by
+/// `(asset_id, timeframe, period_start)`.
+pub struct Resampler {
+    conn: Pool<Postgres>,
+}
+
+impl Resampler {
+    pub fn new(conn: Pool<Postgres>) -> Self {
+        Self { conn }
+    }
+
+    /// Groups `api.asset_prices` rows into `tf`-sized buckets and upserts the
+    /// aggregate into `api.asset_candles`. `open`/`close` are first/last by
+    /// `tstamp` within the bucket, `high`/`low` are max/min, `volume` sums.
+    ///
+    /// Only buckets that contain a row with `tstamp` in `[since, until]` are
+    /// recomputed (each bucket is still aggregated over its *full* range of
+    /// rows, not just the ones in that window) — this keeps a resample
+    /// after a flush an incremental touch-up of recently-written assets
+    /// instead of a full-table rewrite of every historical candle.
+    pub async fn resample(
+        &self,
+        tf: params::Timeframe,
+        since: time::Date,
+        until: time::Date,
+    ) -> Result<PgQueryResult, sqlx::Error> {
+        let (label, bucket) = match tf {
+            params::Timeframe::Week => ("1week", "week"),
+            params::Timeframe::Months(1) => ("1month", "month"),
+            params::Timeframe::Months(n) => {
+                return Err(sqlx::Error::Protocol(format!(
+                    "unsupported resample bucket: {n} months"
+                )));
+            }
+            other => {
+                return Err(sqlx::Error::Protocol(format!(
+                    "{other} is not a resample target, only Week/Months(1) are"
+                )));
+            }
+        };
+
+        sqlx::query(&format!(
+            r#"
+                INSERT INTO api.asset_candles (asset_id, timeframe, period_start, open, close, high, low, volume)
+                SELECT
+                    asset_id,
+                    $1,
+                    date_trunc('{bucket}', tstamp)::date AS period_start,
+                    (array_agg(open ORDER BY tstamp ASC))[1] AS open,
+                    (array_agg(close ORDER BY tstamp DESC))[1] AS close,
+                    max(high) AS high,
+                    min(low) AS low,
+                    sum(volume) AS volume
+                FROM api.asset_prices
+                WHERE (asset_id, date_trunc('{bucket}', tstamp)) IN (
+                    SELECT asset_id, date_trunc('{bucket}', tstamp)
+                    FROM api.asset_prices
+                    WHERE tstamp BETWEEN $2 AND $3
+                )
+                GROUP BY asset_id, period_start
+                ON CONFLICT (asset_id, timeframe, period_start)
+                DO UPDATE SET
+                    open = EXCLUDED.open,
+                    close = EXCLUDED.close,
+                    high = EXCLUDED.high,
+                    low = EXCLUDED.low,
+                    volume = EXCLUDED.volume
+            "#
+        ))
+        .bind(label)
+        .bind(since)
+        .bind(until)
+        .execute(&self.conn)
+        .await
+    }
+}
@@ -0,0 +1,153 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Time-ordered queue of asset ids awaiting their next fetch, replacing the
+/// old single pass over `fetch_assets`. Pops whichever asset's `next_run`
+/// has arrived, and reschedules it with `cadence` on success or with
+/// exponential backoff on failure.
+pub struct Scheduler {
+    queue: BinaryHeap<Reverse<(Instant, i64)>>,
+    cadence: Duration,
+    failures: HashMap<i64, u32>,
+}
+
+impl Scheduler {
+    pub fn new(cadence: Duration) -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+            cadence,
+            failures: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Swaps the steady-state cadence, e.g. when switching between
+    /// market-hours and overnight polling intervals.
+    pub fn set_cadence(&mut self, cadence: Duration) {
+        self.cadence = cadence;
+    }
+
+    /// Refills the queue from a fresh asset list, each due to run immediately.
+    pub fn refill(&mut self, asset_ids: impl IntoIterator<Item = i64>, now: Instant) {
+        for id in asset_ids {
+            self.queue.push(Reverse((now, id)));
+        }
+    }
+
+    /// Returns the next due asset id if its `next_run` has arrived.
+    pub fn pop_due(&mut self, now: Instant) -> Option<i64> {
+        match self.queue.peek() {
+            Some(Reverse((next_run, _))) if *next_run <= now => {
+                let Reverse((_, id)) = self.queue.pop().expect("just peeked");
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Instant the earliest queued asset is due, for sleeping until then.
+    pub fn next_wake(&self) -> Option<Instant> {
+        self.queue.peek().map(|Reverse((next_run, _))| *next_run)
+    }
+
+    /// A fetch for `id` succeeded; requeue it a full cadence out and clear
+    /// its backoff counter.
+    pub fn reschedule(&mut self, id: i64, now: Instant) {
+        self.failures.remove(&id);
+        self.queue.push(Reverse((now + self.cadence, id)));
+    }
+
+    /// A fetch for `id` failed; requeue it with exponential backoff instead
+    /// of dropping it for the rest of the cycle.
+    pub fn reschedule_after_failure(&mut self, id: i64, now: Instant) {
+        let attempt = self.failures.entry(id).or_insert(0);
+        *attempt += 1;
+        let backoff = Duration::from_secs(2u64.saturating_pow(*attempt)).min(self.cadence);
+        self.queue.push(Reverse((now + backoff, id)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pop_due_waits_until_next_run_has_arrived() {
+        let now = Instant::now();
+        let mut scheduler = Scheduler::new(Duration::from_secs(60));
+        scheduler.refill([1], now + Duration::from_secs(10));
+
+        assert_eq!(scheduler.pop_due(now), None);
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(9)), None);
+    }
+
+    #[test]
+    fn pop_due_is_inclusive_of_the_exact_next_run_instant() {
+        let now = Instant::now();
+        let due = now + Duration::from_secs(10);
+        let mut scheduler = Scheduler::new(Duration::from_secs(60));
+        scheduler.refill([1], due);
+
+        assert_eq!(scheduler.pop_due(due), Some(1));
+    }
+
+    #[test]
+    fn pop_due_returns_only_the_earliest_asset() {
+        let now = Instant::now();
+        let mut scheduler = Scheduler::new(Duration::from_secs(60));
+        scheduler.queue.push(Reverse((now + Duration::from_secs(5), 2)));
+        scheduler.queue.push(Reverse((now + Duration::from_secs(1), 1)));
+
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(100)), Some(1));
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(100)), Some(2));
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(100)), None);
+    }
+
+    #[test]
+    fn reschedule_requeues_a_full_cadence_out_and_clears_backoff() {
+        let now = Instant::now();
+        let cadence = Duration::from_secs(60);
+        let mut scheduler = Scheduler::new(cadence);
+
+        scheduler.reschedule_after_failure(1, now);
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(2)), Some(1));
+        scheduler.reschedule(1, now);
+
+        assert_eq!(scheduler.next_wake(), Some(now + cadence));
+        assert!(!scheduler.failures.contains_key(&1));
+    }
+
+    #[test]
+    fn reschedule_after_failure_backs_off_exponentially_and_caps_at_cadence() {
+        let now = Instant::now();
+        let cadence = Duration::from_secs(60);
+        let mut scheduler = Scheduler::new(cadence);
+
+        scheduler.reschedule_after_failure(1, now);
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(2)), Some(1));
+
+        scheduler.reschedule_after_failure(1, now);
+        assert_eq!(scheduler.pop_due(now + Duration::from_secs(4)), Some(1));
+
+        // Keep failing until the backoff would exceed cadence; it must clamp, not overflow.
+        for _ in 0..10 {
+            scheduler.reschedule_after_failure(1, now);
+            assert_eq!(scheduler.pop_due(now + cadence), Some(1));
+        }
+        scheduler.reschedule_after_failure(1, now);
+        assert_eq!(scheduler.next_wake(), Some(now + cadence));
+    }
+
+    #[test]
+    fn is_empty_reflects_queue_state() {
+        let mut scheduler = Scheduler::new(Duration::from_secs(60));
+        assert!(scheduler.is_empty());
+
+        scheduler.refill([1], Instant::now());
+        assert!(!scheduler.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+/// A flattened, serializable copy of the rows `insert_price_frame` would
+/// have written, kept around only long enough to survive a commit failure
+/// and a process restart.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct WalBatch {
+    pub asset_id: Vec<i64>,
+    pub open: Vec<Option<f32>>,
+    pub close: Vec<Option<f32>>,
+    pub high: Vec<Option<f32>>,
+    pub low: Vec<Option<f32>>,
+    pub volume: Vec<Option<i64>>,
+    pub tstamp: Vec<time::Date>,
+}
+
+impl WalBatch {
+    pub fn capture(
+        asset_id: &[i64],
+        open: &[Option<f32>],
+        close: &[Option<f32>],
+        high: &[Option<f32>],
+        low: &[Option<f32>],
+        volume: &[Option<i64>],
+        tstamp: &[time::Date],
+    ) -> Self {
+        Self {
+            asset_id: asset_id.to_vec(),
+            open: open.to_vec(),
+            close: close.to_vec(),
+            high: high.to_vec(),
+            low: low.to_vec(),
+            volume: volume.to_vec(),
+            tstamp: tstamp.to_vec(),
+        }
+    }
+}
+
+/// Append-only newline-delimited JSON log of price batches that failed to
+/// commit after exhausting retries, so `inserter` can flush them and move
+/// on without losing the rows outright. Replayed once on startup.
+pub struct Wal {
+    path: PathBuf,
+}
+
+impl Wal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn spill(&self, batch: &WalBatch) -> Result<(), Box<dyn std::error::Error>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, batch)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads every pending batch and truncates the log. Callers are expected
+    /// to retry each returned batch; if that fails again, call `spill` to
+    /// put it back.
+    pub fn drain(&self) -> Result<Vec<WalBatch>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let reader = BufReader::new(File::open(&self.path)?);
+        let batches = serde_json::Deserializer::from_reader(reader)
+            .into_iter::<WalBatch>()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        std::fs::remove_file(&self.path)?;
+        Ok(batches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn unique_wal() -> Wal {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "updater-wal-test-{}-{}.wal",
+            std::process::id(),
+            n
+        ));
+        Wal::new(path)
+    }
+
+    fn sample_batch() -> WalBatch {
+        WalBatch::capture(
+            &[1, 2],
+            &[Some(1.0), Some(2.0)],
+            &[Some(1.5), Some(2.5)],
+            &[Some(2.0), Some(3.0)],
+            &[Some(0.5), Some(1.5)],
+            &[Some(100), None],
+            &[
+                time::Date::from_calendar_date(2024, time::Month::January, 1).unwrap(),
+                time::Date::from_calendar_date(2024, time::Month::January, 2).unwrap(),
+            ],
+        )
+    }
+
+    #[test]
+    fn drain_on_missing_path_returns_empty() {
+        let wal = unique_wal();
+        assert_eq!(wal.drain().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn spill_then_drain_round_trips_a_batch() {
+        let wal = unique_wal();
+        let batch = sample_batch();
+
+        wal.spill(&batch).unwrap();
+        let mut drained = wal.drain().unwrap();
+
+        assert_eq!(drained.len(), 1);
+        let got = drained.remove(0);
+        assert_eq!(got.asset_id, batch.asset_id);
+        assert_eq!(got.open, batch.open);
+        assert_eq!(got.close, batch.close);
+        assert_eq!(got.high, batch.high);
+        assert_eq!(got.low, batch.low);
+        assert_eq!(got.volume, batch.volume);
+        assert_eq!(got.tstamp, batch.tstamp);
+    }
+
+    #[test]
+    fn spill_appends_and_drain_returns_batches_in_order() {
+        let wal = unique_wal();
+        let first = sample_batch();
+        let second = WalBatch::capture(
+            &[3],
+            &[Some(9.0)],
+            &[Some(9.5)],
+            &[Some(10.0)],
+            &[Some(8.5)],
+            &[Some(50)],
+            &[time::Date::from_calendar_date(2024, time::Month::January, 3).unwrap()],
+        );
+
+        wal.spill(&first).unwrap();
+        wal.spill(&second).unwrap();
+        let drained = wal.drain().unwrap();
+
+        assert_eq!(drained.len(), 2);
+        assert_eq!(drained[0].asset_id, first.asset_id);
+        assert_eq!(drained[1].asset_id, second.asset_id);
+    }
+
+    #[test]
+    fn drain_truncates_the_log() {
+        let wal = unique_wal();
+        wal.spill(&sample_batch()).unwrap();
+
+        assert_eq!(wal.drain().unwrap().len(), 1);
+        assert_eq!(wal.drain().unwrap().len(), 0);
+    }
+}